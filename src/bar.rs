@@ -0,0 +1,115 @@
+//! An i3bar/swaybar protocol output mode: instead of renaming workspaces,
+//! emit one JSON block per focused-workspace update describing its window
+//! list, using the same icon/alias/`remove_duplicates`/`separator` logic as
+//! `update_tree`.
+
+use std::io::{self, Write};
+
+use failure::Error;
+use itertools::Itertools;
+use swayipc::{Connection, Event, EventType, WindowChange, WorkspaceChange};
+
+use crate::{get_classes, get_option, get_workspaces, merge_output_override, Config};
+
+#[derive(Debug, Serialize)]
+pub struct Block {
+    pub full_text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub markup: Option<String>,
+}
+
+/// Name of the focused workspace, via sway's `GET_WORKSPACES` IPC reply
+/// rather than the tree's container `focused` flag: on a tree `Node`,
+/// `focused` is only ever true for the single focused *leaf* window (see
+/// `get_classes`'s use of `node.focused`), not the workspace containing it,
+/// so it's `false` on a workspace whenever any window inside it has focus.
+fn focused_workspace_name(connection: &mut Connection) -> Result<Option<String>, Error> {
+    Ok(connection
+        .get_workspaces()?
+        .into_iter()
+        .find(|workspace| workspace.focused)
+        .map(|workspace| workspace.name))
+}
+
+fn render_block(connection: &mut Connection, config: &Config) -> Result<Block, Error> {
+    let focused_name = focused_workspace_name(connection)?;
+
+    let tree = connection.get_tree()?;
+    let workspace = focused_name.and_then(|name| {
+        get_workspaces(tree)
+            .into_iter()
+            .find(|(_, w)| w.name.as_deref() == Some(name.as_str()))
+    });
+
+    let full_text = match workspace {
+        Some((output, workspace)) => {
+            let config = &merge_output_override(config, &output);
+
+            let separator = match config.general.get("separator") {
+                Some(s) => s.as_str(),
+                None => " | ",
+            };
+
+            let classes = get_classes(&workspace, config);
+            let classes = if get_option(config, "remove_duplicates") {
+                classes.into_iter().unique().collect()
+            } else {
+                classes
+            };
+
+            classes.join(separator)
+        }
+        None => String::new(),
+    };
+
+    Ok(Block {
+        full_text,
+        color: config.general.get("bar_color").cloned(),
+        markup: config.general.get("bar_markup").cloned(),
+    })
+}
+
+fn emit(connection: &mut Connection, config: &Config) -> Result<(), Error> {
+    let block = render_block(connection, config)?;
+    let line = serde_json::to_string(&[block])?;
+    println!("{},", line);
+    io::stdout().flush()?;
+    Ok(())
+}
+
+/// Run the swaybar output mode: print the i3bar protocol header, then one
+/// block per line, re-rendering on every relevant window/workspace event.
+pub fn run(config: &Config) -> Result<(), Error> {
+    println!("{{\"version\":1}}");
+    println!("[");
+
+    let mut render_connection = Connection::new()?;
+    emit(&mut render_connection, config)?;
+
+    let subscribe_connection = Connection::new()?;
+    let events = subscribe_connection.subscribe(&[EventType::Window, EventType::Workspace])?;
+
+    for event in events {
+        match event {
+            Ok(Event::Window(e)) => match e.change {
+                WindowChange::New | WindowChange::Close | WindowChange::Move | WindowChange::Focus => {
+                    emit(&mut render_connection, config)?;
+                }
+                _ => {}
+            },
+            Ok(Event::Workspace(e)) => match e.change {
+                WorkspaceChange::Empty | WorkspaceChange::Focus => {
+                    emit(&mut render_connection, config)?;
+                }
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(())
+}
+
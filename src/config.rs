@@ -0,0 +1,177 @@
+use std::collections::HashMap as Map;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use failure::Error;
+use regex::Regex;
+use toml::Value;
+
+use crate::icons;
+use crate::{Config, OutputOverride};
+
+lazy_static! {
+    pub static ref EMPTY_MAP: Map<String, String> = Map::new();
+    pub static ref EMPTY_OPT_MAP: Map<String, bool> = Map::new();
+    pub static ref EMPTY_ARC_MAP: Arc<Map<String, String>> = Arc::new(Map::new());
+    pub static ref EMPTY_ARC_OUTPUTS: Arc<Map<String, OutputOverride>> = Arc::new(Map::new());
+}
+
+fn parse_string_map(value: &Value, section: &str) -> Map<String, String> {
+    let mut map = Map::new();
+    if let Some(table) = value.get(section).and_then(Value::as_table) {
+        for (key, val) in table {
+            if let Some(s) = val.as_str() {
+                map.insert(key.to_owned(), s.to_owned());
+            }
+        }
+    }
+    map
+}
+
+fn parse_icon_map(value: &Value, section: &str) -> Map<String, char> {
+    let mut map = Map::new();
+    if let Some(table) = value.get(section).and_then(Value::as_table) {
+        for (key, val) in table {
+            if let Some(c) = val.as_str().and_then(|s| s.chars().next()) {
+                map.insert(key.to_owned(), c);
+            }
+        }
+    }
+    map
+}
+
+fn parse_regex_map(value: &Value, section: &str) -> Result<Vec<(Regex, String)>, Error> {
+    let mut out = Vec::new();
+    if let Some(table) = value.get(section).and_then(Value::as_table) {
+        for (pattern, val) in table {
+            if let Some(s) = val.as_str() {
+                out.push((Regex::new(pattern)?, s.to_owned()));
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn parse_icon_regex_map(value: &Value, section: &str) -> Result<Vec<(Regex, char)>, Error> {
+    let mut out = Vec::new();
+    if let Some(table) = value.get(section).and_then(Value::as_table) {
+        for (pattern, val) in table {
+            if let Some(c) = val.as_str().and_then(|s| s.chars().next()) {
+                out.push((Regex::new(pattern)?, c));
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn parse_nested_string_map(value: &Value, section: &str, subsection: &str) -> Map<String, String> {
+    let mut map = Map::new();
+    if let Some(table) = value
+        .get(section)
+        .and_then(Value::as_table)
+        .and_then(|table| table.get(subsection))
+        .and_then(Value::as_table)
+    {
+        for (key, val) in table {
+            if let Some(s) = val.as_str() {
+                map.insert(key.to_owned(), s.to_owned());
+            }
+        }
+    }
+    map
+}
+
+fn parse_nested_icon_map(value: &Value, section: &str, subsection: &str) -> Map<String, char> {
+    let mut map = Map::new();
+    if let Some(table) = value
+        .get(section)
+        .and_then(Value::as_table)
+        .and_then(|table| table.get(subsection))
+        .and_then(Value::as_table)
+    {
+        for (key, val) in table {
+            if let Some(c) = val.as_str().and_then(|s| s.chars().next()) {
+                map.insert(key.to_owned(), c);
+            }
+        }
+    }
+    map
+}
+
+fn parse_outputs(value: &Value) -> Map<String, OutputOverride> {
+    let mut map = Map::new();
+    if let Some(table) = value.get("output").and_then(Value::as_table) {
+        for (name, val) in table {
+            if let Some(sub) = val.as_table() {
+                let over = OutputOverride {
+                    separator: sub.get("separator").and_then(Value::as_str).map(str::to_owned),
+                    default_icon: sub
+                        .get("default_icon")
+                        .and_then(Value::as_str)
+                        .map(str::to_owned),
+                    no_names: sub.get("no_names").and_then(Value::as_bool),
+                    focused_only: sub.get("focused_only").and_then(Value::as_bool),
+                    remove_duplicates: sub.get("remove_duplicates").and_then(Value::as_bool),
+                };
+                map.insert(name.to_owned(), over);
+            }
+        }
+    }
+    map
+}
+
+fn parse_bool_map(value: &Value, section: &str) -> Map<String, bool> {
+    let mut map = Map::new();
+    if let Some(table) = value.get(section).and_then(Value::as_table) {
+        for (key, val) in table {
+            if let Some(b) = val.as_bool() {
+                map.insert(key.to_owned(), b);
+            }
+        }
+    }
+    map
+}
+
+impl Config {
+    /// Parse a `Config` from the contents of a TOML file at `path`.
+    ///
+    /// Missing sections fall back to the same empty defaults used by
+    /// `Config::default`.
+    pub fn new(path: &Path) -> Result<Config, Error> {
+        let contents = fs::read_to_string(path)?;
+        let value: Value = contents.parse::<Value>()?;
+
+        Ok(Config {
+            icons: Arc::new(parse_icon_map(&value, "icons")),
+            icons_regex: Arc::new(parse_icon_regex_map(&value, "icons_regex")?),
+            icons_instance: Arc::new(parse_nested_icon_map(&value, "icons", "instance")),
+            icons_title: Arc::new(parse_nested_icon_map(&value, "icons", "title")),
+            aliases: Arc::new(parse_string_map(&value, "aliases")),
+            aliases_regex: Arc::new(parse_regex_map(&value, "aliases_regex")?),
+            aliases_instance: Arc::new(parse_nested_string_map(&value, "aliases", "instance")),
+            aliases_title: Arc::new(parse_nested_string_map(&value, "aliases", "title")),
+            general: parse_string_map(&value, "general"),
+            options: parse_bool_map(&value, "options"),
+            outputs: Arc::new(parse_outputs(&value)),
+        })
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            icons: icons::NONE.clone(),
+            icons_regex: Arc::new(Vec::new()),
+            icons_instance: Arc::new(Map::new()),
+            icons_title: Arc::new(Map::new()),
+            aliases: EMPTY_ARC_MAP.clone(),
+            aliases_regex: Arc::new(Vec::new()),
+            aliases_instance: EMPTY_ARC_MAP.clone(),
+            aliases_title: EMPTY_ARC_MAP.clone(),
+            general: EMPTY_MAP.clone(),
+            options: EMPTY_OPT_MAP.clone(),
+            outputs: EMPTY_ARC_OUTPUTS.clone(),
+        }
+    }
+}
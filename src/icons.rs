@@ -0,0 +1,10 @@
+use std::collections::HashMap as Map;
+use std::sync::Arc;
+
+lazy_static! {
+    /// Icon map used when the user has not configured any icons and no
+    /// `default_icon` is set, so `get_class` simply falls back to the
+    /// display name. `Arc`-wrapped so `Config::default`/`merge_output_override`
+    /// can share it instead of cloning the (empty, but still allocated) map.
+    pub static ref NONE: Arc<Map<String, char>> = Arc::new(Map::new());
+}
@@ -7,35 +7,96 @@ extern crate failure;
 use failure::Error;
 
 extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
 
 #[macro_use]
 extern crate lazy_static;
 
 extern crate toml;
 
+extern crate regex;
+use regex::Regex;
+
 use swayipc::{Connection, Node, NodeType, WindowChange, WindowEvent, WorkspaceChange, WorkspaceEvent};
 
 use std::collections::HashMap as Map;
+use std::sync::Arc;
 
+pub mod bar;
 pub mod config;
 pub mod icons;
+pub mod socket;
 
 pub struct Config {
-    pub icons: Map<String, char>,
-    pub aliases: Map<String, String>,
+    pub icons: Arc<Map<String, char>>,
+    pub icons_regex: Arc<Vec<(Regex, char)>>,
+    pub icons_instance: Arc<Map<String, char>>,
+    pub icons_title: Arc<Map<String, char>>,
+    pub aliases: Arc<Map<String, String>>,
+    pub aliases_regex: Arc<Vec<(Regex, String)>>,
+    pub aliases_instance: Arc<Map<String, String>>,
+    pub aliases_title: Arc<Map<String, String>>,
     pub general: Map<String, String>,
     pub options: Map<String, bool>,
+    pub outputs: Arc<Map<String, OutputOverride>>,
+}
+
+/// Per-output overrides for an `[output.<name>]` section, merged onto the
+/// base `Config` in `update_tree` before a workspace on that output is
+/// rendered.
+#[derive(Debug, Default, Clone)]
+pub struct OutputOverride {
+    pub separator: Option<String>,
+    pub default_icon: Option<String>,
+    pub no_names: Option<bool>,
+    pub focused_only: Option<bool>,
+    pub remove_duplicates: Option<bool>,
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Config {
-            icons: icons::NONE.clone(),
-            aliases: config::EMPTY_MAP.clone(),
-            general: config::EMPTY_MAP.clone(),
-            options: config::EMPTY_OPT_MAP.clone(),
+/// Clone `config`, overlaying any `[output.<name>]` override for `output`
+/// onto its `general`/`options` maps.
+///
+/// Only `general`/`options` (small, workspace-specific maps) are deep
+/// cloned here; the icon/alias tables are reference-counted (`Arc`) on
+/// `Config`, so sharing them across the per-workspace, per-event copy this
+/// makes is just a refcount bump, not a full map clone.
+pub(crate) fn merge_output_override(config: &Config, output: &str) -> Config {
+    let mut general = config.general.clone();
+    let mut options = config.options.clone();
+
+    if let Some(over) = config.outputs.get(output) {
+        if let Some(separator) = &over.separator {
+            general.insert("separator".to_owned(), separator.clone());
+        }
+        if let Some(default_icon) = &over.default_icon {
+            general.insert("default_icon".to_owned(), default_icon.clone());
+        }
+        if let Some(no_names) = over.no_names {
+            options.insert("no_names".to_owned(), no_names);
+        }
+        if let Some(focused_only) = over.focused_only {
+            options.insert("focused_only".to_owned(), focused_only);
+        }
+        if let Some(remove_duplicates) = over.remove_duplicates {
+            options.insert("remove_duplicates".to_owned(), remove_duplicates);
         }
     }
+
+    Config {
+        icons: Arc::clone(&config.icons),
+        icons_regex: Arc::clone(&config.icons_regex),
+        icons_instance: Arc::clone(&config.icons_instance),
+        icons_title: Arc::clone(&config.icons_title),
+        aliases: Arc::clone(&config.aliases),
+        aliases_regex: Arc::clone(&config.aliases_regex),
+        aliases_instance: Arc::clone(&config.aliases_instance),
+        aliases_title: Arc::clone(&config.aliases_title),
+        general,
+        options,
+        outputs: Arc::clone(&config.outputs),
+    }
 }
 
 #[derive(Debug, Fail)]
@@ -49,32 +110,86 @@ enum LookupError {
     WorkspaceName(Box<Node>),
 }
 
-fn get_option(config: &Config, key: &str) -> bool {
+pub(crate) fn get_option(config: &Config, key: &str) -> bool {
     return match config.options.get(key) {
         Some(v) => *v,
         None => false,
     };
 }
 
-fn get_class(node: &Node, config: &Config) -> Result<String, LookupError> {
-    let name = {
-        match &node.app_id {
+/// Which window property `get_class` keys its lookups on, set via
+/// `general.match_property` (defaults to `class`).
+fn get_match_property(config: &Config) -> &str {
+    match config.general.get("match_property") {
+        Some(property) => property.as_str(),
+        None => "class",
+    }
+}
+
+/// Pull the raw property value off `node` for the configured match property.
+fn get_property(node: &Node, property: &str) -> Option<String> {
+    match property {
+        "instance" => node
+            .window_properties
+            .as_ref()
+            .and_then(|properties| properties.instance.clone()),
+        "title" => node
+            .window_properties
+            .as_ref()
+            .and_then(|properties| properties.title.clone())
+            .or_else(|| node.name.clone()),
+        _ => match &node.app_id {
             Some(id) => Some(id.to_owned()),
-            None => match &node.window_properties {
-                Some(properties) => Some(properties.class.as_ref().unwrap().to_owned()),
-                None => None,
-            },
-        }
-    };
+            None => node
+                .window_properties
+                .as_ref()
+                .and_then(|properties| properties.class.clone()),
+        },
+    }
+}
+
+fn get_class(node: &Node, config: &Config) -> Result<String, LookupError> {
+    let property = get_match_property(config);
+    let name = get_property(node, property);
+
     if let Some(class) = name {
-        let class_display_name = match config.aliases.get(&class) {
+        let (icons, aliases): (&Map<String, char>, &Map<String, String>) = match property {
+            "instance" => (config.icons_instance.as_ref(), config.aliases_instance.as_ref()),
+            "title" => (config.icons_title.as_ref(), config.aliases_title.as_ref()),
+            _ => (config.icons.as_ref(), config.aliases.as_ref()),
+        };
+
+        // `[aliases_regex]`/`[icons_regex]` only ever key on the `class`
+        // property: they predate `match_property`, and switching
+        // `match_property` to `instance`/`title` falls back to an exact
+        // match on `aliases.instance`/`aliases.title` etc. with no regex
+        // equivalent for those properties yet.
+        let class_display_name = match aliases.get(&class) {
             Some(alias) => alias,
+            None if property == "class" => match config
+                .aliases_regex
+                .iter()
+                .find(|(re, _)| re.is_match(&class))
+            {
+                Some((_, alias)) => alias,
+                None => &class,
+            },
             None => &class,
         };
 
         let no_names = get_option(&config, "no_names");
 
-        Ok(match config.icons.get(&class) {
+        let icon = match icons.get(&class) {
+            Some(icon) => Some(icon),
+            None if property == "class" => config
+                .icons_regex
+                .iter()
+                .find(|(re, _)| re.is_match(&class))
+                .map(|(_, icon)| icon),
+            None => None,
+        };
+
+        Ok(match icon {
             Some(icon) => {
                 if no_names {
                     format!("{}", icon)
@@ -100,14 +215,16 @@ fn get_class(node: &Node, config: &Config) -> Result<String, LookupError> {
     }
 }
 
-/// return a collection of workspace nodes
-fn get_workspaces(tree: Node) -> Vec<Node> {
+/// Return a collection of workspace nodes, paired with the name of the
+/// output they belong to.
+pub(crate) fn get_workspaces(tree: Node) -> Vec<(String, Node)> {
     let mut out = Vec::new();
 
     for output in tree.nodes {
+        let output_name = output.name.clone().unwrap_or_default();
         for container in output.nodes {
             if let NodeType::Workspace = container.node_type {
-                out.push(container);
+                out.push((output_name.clone(), container));
             }
         }
     }
@@ -134,7 +251,7 @@ fn get_window_nodes(mut nodes: Vec<Vec<&Node>>) -> Vec<&Node> {
 }
 
 /// Return a collection of window classes
-fn get_classes(workspace: &Node, config: &Config) -> Vec<String> {
+pub(crate) fn get_classes(workspace: &Node, config: &Config) -> Vec<String> {
     let window_nodes = {
         let mut f = get_window_nodes(vec![workspace.floating_nodes.iter().collect()]);
         let mut n = get_window_nodes(vec![workspace.nodes.iter().collect()]);
@@ -169,7 +286,9 @@ fn get_classes(workspace: &Node, config: &Config) -> Vec<String> {
 /// Update all workspace names in tree
 pub fn update_tree(connection: &mut Connection, config: &Config) -> Result<(), Error> {
     let tree = connection.get_tree()?;
-    for workspace in get_workspaces(tree) {
+    for (output, workspace) in get_workspaces(tree) {
+        let config = &merge_output_override(config, &output);
+
         let separator = match config.general.get("separator") {
             Some(s) => s,
             None => " | ",
@@ -189,15 +308,25 @@ pub fn update_tree(connection: &mut Connection, config: &Config) -> Result<(), E
             classes
         };
 
+        let split_at = match config.general.get("split_at") {
+            Some(s) => s.as_str(),
+            None => " ",
+        };
+
         let old: String = workspace
             .name
             .to_owned()
             .ok_or_else(|| LookupError::WorkspaceName(Box::new(workspace)))?;
 
-        let mut new = old.split(' ').next().unwrap().to_owned();
+        let mut new = old.split(split_at).next().unwrap_or(&old).to_owned();
 
         if !classes.is_empty() {
-            new.push_str(&classes);
+            if get_option(&config, "keep_split_at") {
+                new.push_str(split_at);
+                new.push_str(classes.trim_start());
+            } else {
+                new.push_str(&classes);
+            }
         }
 
         if old != new {
@@ -0,0 +1,97 @@
+extern crate swaywsr;
+extern crate swayipc;
+
+use std::env;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use swayipc::{Connection, Event, EventType};
+use swaywsr::socket::{self, Command};
+use swaywsr::{handle_window_event, handle_workspace_event, update_tree, Config};
+
+fn default_config_path() -> Option<PathBuf> {
+    let mut path = match env::var("XDG_CONFIG_HOME") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => PathBuf::from(env::var("HOME").ok()?).join(".config"),
+    };
+    path.push("swaywsr");
+    path.push("config.toml");
+    Some(path)
+}
+
+fn load_config(path: &Option<PathBuf>) -> Config {
+    match path {
+        Some(path) if path.exists() => Config::new(path).unwrap_or_else(|e| {
+            eprintln!("failed to parse config, using defaults: {}", e);
+            Config::default()
+        }),
+        _ => Config::default(),
+    }
+}
+
+/// Send a command to an already running daemon and exit.
+fn run_client(command: Command) -> ! {
+    if let Err(e) = socket::send_command(&command) {
+        eprintln!("failed to send command: {}", e);
+        std::process::exit(1);
+    }
+    std::process::exit(0);
+}
+
+fn main() {
+    match env::args().nth(1).as_deref() {
+        Some("reload") => run_client(Command::Reload),
+        Some("refresh") => run_client(Command::Refresh),
+        Some("bar") => {
+            let config = load_config(&default_config_path());
+            if let Err(e) = swaywsr::bar::run(&config) {
+                eprintln!("bar error: {}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    let config_path = default_config_path();
+    let config = Arc::new(RwLock::new(load_config(&config_path)));
+
+    let mut connection = Connection::new().expect("failed to connect to sway");
+    if let Err(e) = update_tree(&mut connection, &config.read().unwrap()) {
+        eprintln!("update_tree error: {}", e);
+    }
+
+    {
+        let config = Arc::clone(&config);
+        let mut refresh_connection = Connection::new().expect("failed to connect to sway");
+        socket::listen(Arc::clone(&config), config_path, move |_command| {
+            if let Err(e) = update_tree(&mut refresh_connection, &config.read().unwrap()) {
+                eprintln!("update_tree error: {}", e);
+            }
+        })
+        .expect("failed to start control socket");
+    }
+
+    let subscribe_connection = Connection::new().expect("failed to connect to sway");
+    let events = subscribe_connection
+        .subscribe(&[EventType::Window, EventType::Workspace])
+        .expect("failed to subscribe to sway events");
+
+    let mut event_connection = Connection::new().expect("failed to connect to sway");
+    for event in events {
+        let result = match event {
+            Ok(Event::Window(e)) => {
+                handle_window_event(&e, &mut event_connection, &config.read().unwrap())
+            }
+            Ok(Event::Workspace(e)) => {
+                handle_workspace_event(&e, &mut event_connection, &config.read().unwrap())
+            }
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.into()),
+        };
+
+        if let Err(e) = result {
+            eprintln!("event handling error: {}", e);
+        }
+    }
+}
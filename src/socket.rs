@@ -0,0 +1,99 @@
+//! A small Unix domain control socket that lets a running `swaywsr` daemon
+//! be told to reload its config or refresh its render without restarting
+//! the process.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+use failure::Error;
+
+use crate::Config;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Command {
+    /// Re-parse the config file from disk and swap it into the running state.
+    Reload,
+    /// Force a re-render without waiting for a sway event.
+    Refresh,
+}
+
+/// Path of the control socket, namespaced under `$XDG_RUNTIME_DIR` (falling
+/// back to `/tmp` when that isn't set).
+pub fn socket_path() -> PathBuf {
+    let runtime_dir = std::env::var("XDG_RUNTIME_DIR").unwrap_or_else(|_| "/tmp".to_owned());
+    PathBuf::from(runtime_dir).join("swaywsr.sock")
+}
+
+/// Send `command` to a running daemon's control socket.
+pub fn send_command(command: &Command) -> Result<(), Error> {
+    let mut stream = UnixStream::connect(socket_path())?;
+    let payload = serde_json::to_string(command)?;
+    writeln!(stream, "{}", payload)?;
+    Ok(())
+}
+
+/// Bind the control socket and, on a background thread, apply incoming
+/// `Reload` commands to `config` before handing every command to `on_command`
+/// so the caller can trigger a re-render.
+///
+/// `on_command` takes `&mut self` (`FnMut`) rather than `Fn` because callers
+/// typically close over a `Connection`, and re-rendering through it requires
+/// a mutable borrow.
+///
+/// Note: the socket file is only ever removed up front (so a stale one left
+/// behind by a crashed daemon doesn't block `bind`); there is no handler to
+/// unlink it again on a clean shutdown or signal.
+pub fn listen<F>(
+    config: Arc<RwLock<Config>>,
+    config_path: Option<PathBuf>,
+    mut on_command: F,
+) -> Result<(), Error>
+where
+    F: FnMut(Command) + Send + 'static,
+{
+    let path = socket_path();
+    let _ = fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)?;
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("control socket accept error: {}", e);
+                    continue;
+                }
+            };
+
+            for line in BufReader::new(stream).lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => continue,
+                };
+
+                let command: Command = match serde_json::from_str(&line) {
+                    Ok(command) => command,
+                    Err(e) => {
+                        eprintln!("control socket: invalid command: {}", e);
+                        continue;
+                    }
+                };
+
+                if let (Command::Reload, Some(path)) = (&command, &config_path) {
+                    match Config::new(path) {
+                        Ok(new_config) => *config.write().unwrap() = new_config,
+                        Err(e) => eprintln!("failed to reload config: {}", e),
+                    }
+                }
+
+                on_command(command);
+            }
+        }
+    });
+
+    Ok(())
+}